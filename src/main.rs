@@ -7,36 +7,98 @@ use handlebars::Handlebars;
 use warp::Filter;
 
 const TEMPLATE_INDEX: &str = "index";
+const TEMPLATE_YEAR: &str = "year";
+
+// преобразовать строковое представление дня недели (напр. "sunday") в Weekday
+fn parse_week_start(s: &str) -> Option<Weekday> {
+    match s.to_lowercase().as_str() {
+        "monday"    => Some(Weekday::Mon),
+        "tuesday"   => Some(Weekday::Tue),
+        "wednesday" => Some(Weekday::Wed),
+        "thursday"  => Some(Weekday::Thu),
+        "friday"    => Some(Weekday::Fri),
+        "saturday"  => Some(Weekday::Sat),
+        "sunday"    => Some(Weekday::Sun),
+        _ => None
+    }
+}
+
+// язык, на котором подписываются названия месяцев
+#[derive(Debug, Clone, Copy)]
+enum Locale {
+    Ru,
+    En
+}
+
+impl Locale {
+    // преобразовать строковое представление языка (напр. "en") в Locale
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "ru" => Some(Locale::Ru),
+            "en" => Some(Locale::En),
+            _ => None
+        }
+    }
+
+    // таблица названий месяцев по порядку, от января к декабрю
+    fn month_names(self) -> [&'static str; 12] {
+        match self {
+            Locale::Ru => ["Январь", "Февраль", "Март", "Апрель", "Май", "Июнь",
+                "Июль", "Август", "Сентябрь", "Октябрь", "Ноябрь", "Декабрь"],
+            Locale::En => ["January", "February", "March", "April", "May", "June",
+                "July", "August", "September", "October", "November", "December"]
+        }
+    }
+}
 
 
 // данная структура безопасно сериализуется и копируется
 #[derive(Serialize, Deserialize, Debug, Clone)]        
 // структура хранение данных о дне календаря
-struct Day {                                           
+struct Day {
     // текст в ячейке календаря
-    txt: String,                                       
+    txt: String,
     // является ли красным днём
-    red: bool                                          
+    red: bool,
+    // является ли этот день сегодняшним
+    today: bool,
+    // подписи пользовательских событий, назначенных на этот день
+    events: Vec<String>
 }
 
 // объявление дружественных функций для структуры Day
 impl Day {
     // создаёт пустую ячуйку календаря
     fn empty() -> Self {
-        return Day { txt: "".to_string(), red: false };
+        return Day { txt: "".to_string(), red: false, today: false, events: vec![] };
     }
 }
 // обьявляет возможность создания Day из структуры NaiveDate
 // день календаря красный, если в NaiveDate указан как сб или вс
 impl From<NaiveDate> for Day {
     fn from(date: NaiveDate) -> Self {
-        return Day { 
-            txt: date.day().to_string(), 
+        return Day {
+            txt: date.day().to_string(),
             red: date.weekday() == Weekday::Sun || date.weekday() == Weekday::Sat,
+            today: false,
+            events: vec![],
         };
     }
 }
 
+// аннотация пользовательского события или праздника, привязанная к дате
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct DayAnnotation {
+    // текст события, отображаемый в ячейке
+    label: String,
+    // является ли этот день праздником (принудительно делает его красным)
+    #[serde(default)]
+    holiday: bool
+}
+
+// таблица аннотаций, загруженная из конфигурационного файла при запуске
+type Annotations = Arc<HashMap<NaiveDate, DayAnnotation>>;
+
 // структура для хранения месяца
 #[derive(Serialize, Deserialize, Debug)]
 struct Month {
@@ -48,14 +110,23 @@ struct Month {
 
 impl Month {
     // конструктор календарного месяца
-    // принимает номер месяца и год
-    fn new(order: u32, year: i32) -> Option<Self> {
+    // принимает номер месяца, год, день недели, с которого начинается неделя,
+    // сегодняшнюю дату (для подсветки текущего дня), локаль названия месяца
+    // и таблицу аннотаций (события и праздники) для наложения на сетку
+    fn new(order: u32, year: i32, week_start: Weekday, today: NaiveDate, locale: Locale, annotations: &Annotations) -> Option<Self> {
+        // отклонить недопустимые месяц/год до обращения к NaiveDate::from_ymd,
+        // которая паникует на некорректном вводе вместо возврата Option
+        if !(1..=12).contains(&order) || !(-9999..=9999).contains(&year) {
+            return None;
+        }
         // найти первый день месяца в библиотеке chrono
         let date = NaiveDate::from_ymd(year, order, 1);
         // найти месяц по номеру
         let month = chrono::Month::from_u32(date.month())?;
-        // определить день недели первого дня месяцв
-        let weekday = date.weekday().number_from_monday();
+        // определить сдвиг первого дня месяца относительно недели,
+        // начинающейся с week_start
+        let offset = (date.weekday().num_days_from_sunday() + 7
+            - week_start.num_days_from_sunday()) % 7;
         // определить следующий месяц
         let next_month = month.succ().number_from_month();
         // определить, находится ли следующий месяц в следующем году
@@ -71,36 +142,31 @@ impl Month {
             .signed_duration_since(date)
             .num_days();
 
-        // сопоставить перечисление месяцев с русским представлением
-        let month_rus = match month {
-            chrono::Month::January  => "Январь",
-            chrono::Month::February => "Февраль",
-            chrono::Month::March    => "Март",
-            chrono::Month::April    => "Апрель",
-            chrono::Month::May      => "Май",
-            chrono::Month::June     => "Июнь",
-            chrono::Month::July     => "Июль",
-            chrono::Month::August   => "Август",
-            chrono::Month::September=> "Сентябрь",
-            chrono::Month::October  => "Октябрь",
-            chrono::Month::November => "Ноябрь",
-            chrono::Month::December => "Декабрь"
-        };
+        // найти название месяца в таблице, соответствующей выбранной локали
+        let month_localized = locale.month_names()[month.number_from_month() as usize - 1];
 
         // название месяца - это месяц + год
-        let month_name = format!("{} {}", month_rus, year);
+        let month_name = format!("{} {}", month_localized, year);
         
         // создание таблицы календарных дней
         // повторять None (аналог null)...
         let rows = iter::repeat(None)
             // ...чтобы сдвинуть первый день недели
-            .take((weekday - 1) as usize)
+            .take(offset as usize)
             // и добавить определённое количество дней от 1 до N
             .chain((1..=days_in_month).map(|e| Some(e)))
             // превратить эти дни в даты из chrono
             .map(|o| o.map(|day| NaiveDate::from_ymd(year, order, day as u32)))
-            // с помощью конструктора заменить даты на ячейки (заменяя null на пустые)
-            .map(|o| o.map_or_else(Day::empty, Day::from))
+            // с помощью конструктора заменить даты на ячейки (заменяя null на пустые),
+            // отметить сегодняшний день и наложить аннотации событий/праздников
+            .map(|o| o.map_or_else(Day::empty, |date| {
+                let mut day = Day { today: date == today, ..Day::from(date) };
+                if let Some(annotation) = annotations.get(&date) {
+                    day.events.push(annotation.label.clone());
+                    day.red = day.red || annotation.holiday;
+                }
+                return day;
+            }))
             .collect::<Vec<Day>>()
             // разделить на кусочки по 7 дней
             .chunks(7)
@@ -111,7 +177,7 @@ impl Month {
         return Some(Month { name: month_name, days: rows });
     }
     // для создания из строки типа `2021-03`
-    fn from_date_notation(date_notation: &String) -> Option<Self> {
+    fn from_date_notation(date_notation: &String, week_start: Weekday, today: NaiveDate, locale: Locale, annotations: &Annotations) -> Option<Self> {
         let mut iter = date_notation
                 // разделить по символу `-`
                 .split("-")
@@ -122,7 +188,27 @@ impl Month {
         // второе число - месяц
         let month = iter.next()?? as u32;
         // использовать стандартный конструктор
-        return Self::new(month,year);
+        return Self::new(month, year, week_start, today, locale, annotations);
+    }
+}
+
+// структура для хранения целого года, состоящего из двенадцати месяцев
+#[derive(Serialize, Deserialize, Debug)]
+struct Year {
+    // численное обозначение года в заголовке
+    year: i32,
+    // список всех месяцев года по порядку
+    months: Vec<Month>
+}
+
+impl Year {
+    // конструктор календарного года
+    // строит все 12 месяцев, вызывая Month::new по очереди
+    fn new(year: i32, week_start: Weekday, today: NaiveDate, locale: Locale, annotations: &Annotations) -> Option<Self> {
+        let months = (1..=12)
+            .map(|order| Month::new(order, year, week_start, today, locale, annotations))
+            .collect::<Option<Vec<Month>>>()?;
+        return Some(Year { year, months });
     }
 }
 
@@ -132,35 +218,79 @@ async fn main() {
     let mut handlebars = Handlebars::new();                
 
     // зареестрировать темплейт из файла
-    handlebars                                             
+    handlebars
         .register_template_file(TEMPLATE_INDEX, "templates/index.hbs")
         // вывести ошибку если файл не найден
-        .expect("Could not load template file index.hbs"); 
+        .expect("Could not load template file index.hbs");
+
+    // зарегистрировать темплейт для отображения целого года
+    handlebars
+        .register_template_file(TEMPLATE_YEAR, "templates/year.hbs")
+        .expect("Could not load template file year.hbs");
+
+    let handlebars = Arc::new(handlebars);
 
-    let handlebars = Arc::new(handlebars);                 
+    // загрузить пользовательские аннотации (события и праздники) из конфигурационного
+    // файла, ключом служит дата в формате `YYYY-MM-DD` (требует фичу `serde` у chrono);
+    // отсутствие файла не является ошибкой, но битый JSON выводится в stderr
+    let annotations: Annotations = Arc::new(match std::fs::read_to_string("annotations.json") {
+        Ok(raw) => serde_json::from_str::<HashMap<NaiveDate, DayAnnotation>>(&raw)
+            .map_err(|e| eprintln!("Could not parse annotations.json: {}", e))
+            .unwrap_or_default(),
+        Err(_) => HashMap::new()
+    });
 
     // объявить путь GET
-    let route = warp::get()                                
-        .and(warp::path::end())                            
+    let route = warp::get()
+        .and(warp::path::end())
         // получить список параметров в виде ассоциативного массива
         .and(warp::query::<HashMap<String, String>>())
-        .map(move |p: HashMap<String,String>| { 
+        .map(move |p: HashMap<String,String>| {
+            // поймать аннотации в замыкание по ссылке на общий Arc
+            let annotations = &annotations;
             // получить значение параметра запроса
             let month = p.get("month");
-            let month = match month { 
-                // если параметр доступен - создать объект календарного месяца
-                Some(month) => Month::from_date_notation(month),
-                None => None
-            // если месяц не доступен - создать месяц по умолчанию
-            // Алексей Метлицкий; дата рождения - 21.03.2000
-            }.unwrap_or(Month::new(3,2017).unwrap());
-            let rendered = handlebars
-                // обработка шаблона
-                .render(TEMPLATE_INDEX, &month)            
-                // если произошла ошибка - вывести её вместо результата
-                .unwrap_or_else(|e| e.to_string());        
+            let year = p.get("year");
+            // получить день недели, с которого должна начинаться неделя
+            let week_start = p.get("week_start")
+                .and_then(|s| parse_week_start(s))
+                .unwrap_or(Weekday::Mon);
+            // сегодняшняя дата, используемая для подсветки текущего дня
+            let today = chrono::Local::now().naive_local().date();
+            // получить локаль названий месяцев, по умолчанию русская
+            let locale = p.get("lang")
+                .and_then(|s| Locale::parse(s))
+                .unwrap_or(Locale::Ru);
+            // если указан месяц - отобразить один месяц
+            // если указан только год - отобразить все двенадцать месяцев
+            let rendered = match month {
+                Some(month) => {
+                    let month = Month::from_date_notation(month, week_start, today, locale, annotations)
+                        // если месяц не доступен - показать текущий месяц
+                        .unwrap_or(Month::new(today.month(), today.year(), week_start, today, locale, annotations).unwrap());
+                    handlebars.render(TEMPLATE_INDEX, &month)
+                },
+                None => match year {
+                    Some(year) => {
+                        let year = year.parse::<i32>().ok()
+                            // отбросить годы вне диапазона -9999..=9999
+                            // (для них Month::new и так вернул бы None)
+                            .filter(|year| (-9999..=9999).contains(year))
+                            .and_then(|year| Year::new(year, week_start, today, locale, annotations))
+                            // если год не доступен - показать текущий год
+                            .unwrap_or(Year::new(today.year(), week_start, today, locale, annotations).unwrap());
+                        handlebars.render(TEMPLATE_YEAR, &year)
+                    },
+                    None => {
+                        // без параметров - показать текущий месяц
+                        let month = Month::new(today.month(), today.year(), week_start, today, locale, annotations).unwrap();
+                        handlebars.render(TEMPLATE_INDEX, &month)
+                    }
+                }
+            // если произошла ошибка - вывести её вместо результата
+            }.unwrap_or_else(|e| e.to_string());
             // обернуть в http ответ
-            return warp::reply::html(rendered);            
+            return warp::reply::html(rendered);
 
         });
 